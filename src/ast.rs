@@ -0,0 +1,107 @@
+//! The expression/statement AST produced by the parser and consumed by
+//! the interpreter.
+
+use std::ops::Range;
+
+use crate::lexer::Token;
+use crate::variables::Value;
+
+/// An identifier, e.g. a variable or function name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Iden(pub String);
+
+/// A binary operator boxed up as a callable value via the `\` sigil (e.g.
+/// `\+`, `\<`, `\&`), so it can be passed around like any other function.
+///
+/// Only operators that take two `Value::Int` operands are boxable (the
+/// comparisons among them produce a `Value::Bool`); prefix-only operators
+/// such as `Not` have no infix form to box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoxedOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Modulo,
+    Exponent,
+    FloorDivide,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Lt,
+    Gt,
+    Eq,
+    Neq,
+}
+
+impl BoxedOp {
+    /// The operator a `\`-prefixed token refers to, or `None` if that token
+    /// has no boxable infix form.
+    pub fn from_token(token: &Token) -> Option<Self> {
+        Some(match token {
+            Token::Addition => Self::Add,
+            Token::Subtract => Self::Subtract,
+            Token::Multiply => Self::Multiply,
+            Token::Divide => Self::Divide,
+            Token::Modulo => Self::Modulo,
+            Token::Exponent => Self::Exponent,
+            Token::FloorDivide => Self::FloorDivide,
+            Token::BitAnd => Self::BitAnd,
+            Token::BitOr => Self::BitOr,
+            Token::BitXor => Self::BitXor,
+            Token::OpLt => Self::Lt,
+            Token::OpGt => Self::Gt,
+            Token::OpEq => Self::Eq,
+            Token::OpNeq => Self::Neq,
+            _ => return None,
+        })
+    }
+}
+
+/// The shape of an expression, independent of where it was parsed from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExprKind {
+    Literal(Value),
+    Identifier(Iden),
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    OpFunction(BoxedOp),
+    Add { left: Box<Expr>, right: Box<Expr> },
+    Subtract { left: Box<Expr>, right: Box<Expr> },
+    Multiply { left: Box<Expr>, right: Box<Expr> },
+    Divide { left: Box<Expr>, right: Box<Expr> },
+    Modulo { left: Box<Expr>, right: Box<Expr> },
+    Exponent { left: Box<Expr>, right: Box<Expr> },
+    FloorDivide { left: Box<Expr>, right: Box<Expr> },
+    BitAnd { left: Box<Expr>, right: Box<Expr> },
+    BitOr { left: Box<Expr>, right: Box<Expr> },
+    BitXor { left: Box<Expr>, right: Box<Expr> },
+    Lt { left: Box<Expr>, right: Box<Expr> },
+    Gt { left: Box<Expr>, right: Box<Expr> },
+    Eq { left: Box<Expr>, right: Box<Expr> },
+    Neq { left: Box<Expr>, right: Box<Expr> },
+    And { left: Box<Expr>, right: Box<Expr> },
+    Or { left: Box<Expr>, right: Box<Expr> },
+}
+
+/// An expression together with the source span it was parsed from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Range<usize>,
+}
+
+impl Expr {
+    pub fn new(kind: ExprKind, span: Range<usize>) -> Self {
+        Expr { kind, span }
+    }
+}
+
+/// A statement, the unit `Parser::parse` produces one of per top-level
+/// construct.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stmt {
+    Print(Expr),
+    VarAssignment { iden: Iden, value: Expr },
+    FunctionCall { iden: Iden, args: Vec<Expr> },
+}
@@ -0,0 +1,32 @@
+//! Parser/interpreter error types.
+
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnexpectedToken,
+    UnexpectedEof,
+    InvalidIdentifier,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub kind: ErrorKind,
+    pub span: Range<usize>,
+}
+
+impl Error {
+    pub fn unexpected_token(span: Range<usize>) -> Self {
+        Error {
+            kind: ErrorKind::UnexpectedToken,
+            span,
+        }
+    }
+
+    pub fn end_of_token_stream() -> Self {
+        Error {
+            kind: ErrorKind::UnexpectedEof,
+            span: 0..0,
+        }
+    }
+}
@@ -0,0 +1,392 @@
+//! Tree-walking evaluator for the AST produced by the parser.
+
+use std::collections::HashMap;
+
+use crate::ast::{BoxedOp, Expr, ExprKind, Stmt};
+use crate::variables::Value;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExecErrorKind {
+    UndefinedVariable,
+    TypeMismatch,
+    DivideByZero,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecError {
+    pub kind: ExecErrorKind,
+}
+
+impl ExecError {
+    fn new(kind: ExecErrorKind) -> Self {
+        ExecError { kind }
+    }
+}
+
+type EvalResult = Result<Value, ExecError>;
+
+/// Holds variable bindings and executes a parsed AbleScript program.
+#[derive(Default)]
+pub struct Interpreter {
+    scope: HashMap<String, Value>,
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        Interpreter::default()
+    }
+
+    pub fn run(&mut self, stmts: &[Stmt]) -> Result<(), ExecError> {
+        for stmt in stmts {
+            self.exec(stmt)?;
+        }
+        Ok(())
+    }
+
+    fn exec(&mut self, stmt: &Stmt) -> Result<(), ExecError> {
+        match stmt {
+            Stmt::Print(expr) => {
+                let value = self.eval(expr)?;
+                println!("{:?}", value);
+                Ok(())
+            }
+            Stmt::VarAssignment { iden, value } => {
+                let value = self.eval(value)?;
+                self.scope.insert(iden.0.clone(), value);
+                Ok(())
+            }
+            Stmt::FunctionCall { iden, args } => {
+                let callee = self
+                    .scope
+                    .get(&iden.0)
+                    .cloned()
+                    .ok_or_else(|| ExecError::new(ExecErrorKind::UndefinedVariable))?;
+                if let Value::BoxedOp(op) = callee {
+                    // A boxed operator is callable with exactly the two
+                    // operands it was folded from as an infix expression.
+                    if args.len() != 2 {
+                        return Err(ExecError::new(ExecErrorKind::TypeMismatch));
+                    }
+                    let left = self.eval(&args[0])?;
+                    let right = self.eval(&args[1])?;
+                    self.apply_boxed_op(op, left, right)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Apply a boxed infix operator to two already-evaluated operands, the
+    /// same way `fold_infix`'s generated `Expr` variants would evaluate.
+    /// Both operands must be `Value::Int`: unlike `Expr::Eq`/`Expr::Neq`, a
+    /// boxed `Eq`/`Neq` gives a `TypeMismatch` for any other operand type.
+    fn apply_boxed_op(&self, op: BoxedOp, left: Value, right: Value) -> EvalResult {
+        let (l, r) = match (left, right) {
+            (Value::Int(l), Value::Int(r)) => (l, r),
+            _ => return Err(ExecError::new(ExecErrorKind::TypeMismatch)),
+        };
+
+        match op {
+            BoxedOp::Add => l
+                .checked_add(r)
+                .map(Value::Int)
+                .ok_or_else(|| ExecError::new(ExecErrorKind::TypeMismatch)),
+            BoxedOp::Subtract => l
+                .checked_sub(r)
+                .map(Value::Int)
+                .ok_or_else(|| ExecError::new(ExecErrorKind::TypeMismatch)),
+            BoxedOp::Multiply => l
+                .checked_mul(r)
+                .map(Value::Int)
+                .ok_or_else(|| ExecError::new(ExecErrorKind::TypeMismatch)),
+            BoxedOp::Divide => {
+                if r == 0 {
+                    return Err(ExecError::new(ExecErrorKind::DivideByZero));
+                }
+                l.checked_div(r)
+                    .map(Value::Int)
+                    .ok_or_else(|| ExecError::new(ExecErrorKind::TypeMismatch))
+            }
+            BoxedOp::Modulo => {
+                if r == 0 {
+                    return Err(ExecError::new(ExecErrorKind::DivideByZero));
+                }
+                l.checked_rem(r)
+                    .map(Value::Int)
+                    .ok_or_else(|| ExecError::new(ExecErrorKind::TypeMismatch))
+            }
+            BoxedOp::Exponent => {
+                if r < 0 {
+                    return Err(ExecError::new(ExecErrorKind::TypeMismatch));
+                }
+                l.checked_pow(r as u32)
+                    .map(Value::Int)
+                    .ok_or_else(|| ExecError::new(ExecErrorKind::TypeMismatch))
+            }
+            BoxedOp::FloorDivide => {
+                if r == 0 {
+                    return Err(ExecError::new(ExecErrorKind::DivideByZero));
+                }
+                Ok(Value::Int(floor_div(l, r)))
+            }
+            BoxedOp::BitAnd => Ok(Value::Int(l & r)),
+            BoxedOp::BitOr => Ok(Value::Int(l | r)),
+            BoxedOp::BitXor => Ok(Value::Int(l ^ r)),
+            BoxedOp::Lt => Ok(Value::Bool(l < r)),
+            BoxedOp::Gt => Ok(Value::Bool(l > r)),
+            BoxedOp::Eq => Ok(Value::Bool(l == r)),
+            BoxedOp::Neq => Ok(Value::Bool(l != r)),
+        }
+    }
+
+    pub fn eval(&mut self, expr: &Expr) -> EvalResult {
+        match &expr.kind {
+            ExprKind::Literal(value) => Ok(value.clone()),
+            ExprKind::Identifier(iden) => self
+                .scope
+                .get(&iden.0)
+                .cloned()
+                .ok_or_else(|| ExecError::new(ExecErrorKind::UndefinedVariable)),
+            ExprKind::OpFunction(op) => Ok(Value::BoxedOp(*op)),
+            ExprKind::Not(operand) => match self.eval(operand)? {
+                Value::Bool(b) => Ok(Value::Bool(!b)),
+                _ => Err(ExecError::new(ExecErrorKind::TypeMismatch)),
+            },
+            ExprKind::Neg(operand) => match self.eval(operand)? {
+                Value::Int(i) => i
+                    .checked_neg()
+                    .map(Value::Int)
+                    .ok_or_else(|| ExecError::new(ExecErrorKind::TypeMismatch)),
+                _ => Err(ExecError::new(ExecErrorKind::TypeMismatch)),
+            },
+            ExprKind::Add { left, right } => self.eval_int_op(left, right, i32::checked_add),
+            ExprKind::Subtract { left, right } => self.eval_int_op(left, right, i32::checked_sub),
+            ExprKind::Multiply { left, right } => self.eval_int_op(left, right, i32::checked_mul),
+            ExprKind::Divide { left, right } => self.eval_div(left, right),
+            ExprKind::Modulo { left, right } => self.eval_checked_div(left, right, i32::checked_rem),
+            ExprKind::Exponent { left, right } => self.eval_exponent(left, right),
+            ExprKind::FloorDivide { left, right } => self.eval_floor_div(left, right),
+            ExprKind::BitAnd { left, right } => self.eval_int_op(left, right, |l, r| Some(l & r)),
+            ExprKind::BitOr { left, right } => self.eval_int_op(left, right, |l, r| Some(l | r)),
+            ExprKind::BitXor { left, right } => self.eval_int_op(left, right, |l, r| Some(l ^ r)),
+            ExprKind::Lt { left, right } => self.eval_cmp(left, right, |l, r| l < r),
+            ExprKind::Gt { left, right } => self.eval_cmp(left, right, |l, r| l > r),
+            ExprKind::Eq { left, right } => {
+                let (l, r) = (self.eval(left)?, self.eval(right)?);
+                Ok(Value::Bool(l == r))
+            }
+            ExprKind::Neq { left, right } => {
+                let (l, r) = (self.eval(left)?, self.eval(right)?);
+                Ok(Value::Bool(l != r))
+            }
+            ExprKind::And { left, right } => self.eval_bool_op(left, right, |l, r| l && r),
+            ExprKind::Or { left, right } => self.eval_bool_op(left, right, |l, r| l || r),
+        }
+    }
+
+    fn eval_int(&mut self, expr: &Expr) -> Result<i32, ExecError> {
+        match self.eval(expr)? {
+            Value::Int(i) => Ok(i),
+            _ => Err(ExecError::new(ExecErrorKind::TypeMismatch)),
+        }
+    }
+
+    fn eval_int_op(
+        &mut self,
+        left: &Expr,
+        right: &Expr,
+        op: fn(i32, i32) -> Option<i32>,
+    ) -> EvalResult {
+        let (l, r) = (self.eval_int(left)?, self.eval_int(right)?);
+        op(l, r)
+            .map(Value::Int)
+            .ok_or_else(|| ExecError::new(ExecErrorKind::TypeMismatch))
+    }
+
+    fn eval_div(&mut self, left: &Expr, right: &Expr) -> EvalResult {
+        self.eval_checked_div(left, right, i32::checked_div)
+    }
+
+    fn eval_checked_div(
+        &mut self,
+        left: &Expr,
+        right: &Expr,
+        op: fn(i32, i32) -> Option<i32>,
+    ) -> EvalResult {
+        let (l, r) = (self.eval_int(left)?, self.eval_int(right)?);
+        if r == 0 {
+            return Err(ExecError::new(ExecErrorKind::DivideByZero));
+        }
+        op(l, r)
+            .map(Value::Int)
+            .ok_or_else(|| ExecError::new(ExecErrorKind::TypeMismatch))
+    }
+
+    fn eval_floor_div(&mut self, left: &Expr, right: &Expr) -> EvalResult {
+        let (l, r) = (self.eval_int(left)?, self.eval_int(right)?);
+        if r == 0 {
+            return Err(ExecError::new(ExecErrorKind::DivideByZero));
+        }
+        Ok(Value::Int(floor_div(l, r)))
+    }
+
+    fn eval_exponent(&mut self, left: &Expr, right: &Expr) -> EvalResult {
+        let (l, r) = (self.eval_int(left)?, self.eval_int(right)?);
+        if r < 0 {
+            return Err(ExecError::new(ExecErrorKind::TypeMismatch));
+        }
+        l.checked_pow(r as u32)
+            .map(Value::Int)
+            .ok_or_else(|| ExecError::new(ExecErrorKind::TypeMismatch))
+    }
+
+    fn eval_cmp(&mut self, left: &Expr, right: &Expr, op: fn(i32, i32) -> bool) -> EvalResult {
+        let (l, r) = (self.eval_int(left)?, self.eval_int(right)?);
+        Ok(Value::Bool(op(l, r)))
+    }
+
+    fn eval_bool_op(
+        &mut self,
+        left: &Expr,
+        right: &Expr,
+        op: fn(bool, bool) -> bool,
+    ) -> EvalResult {
+        let (l, r) = (self.eval(left)?, self.eval(right)?);
+        match (l, r) {
+            (Value::Bool(l), Value::Bool(r)) => Ok(Value::Bool(op(l, r))),
+            _ => Err(ExecError::new(ExecErrorKind::TypeMismatch)),
+        }
+    }
+}
+
+/// Integer floor division, rounding toward negative infinity (unlike
+/// truncating `/`): `-7 // 2` is `-4`, not `-3`. Shared by `Expr::FloorDivide`
+/// evaluation and boxed-operator application.
+fn floor_div(l: i32, r: i32) -> i32 {
+    let quotient = l / r;
+    let remainder = l % r;
+    if remainder != 0 && (remainder < 0) != (r < 0) {
+        quotient - 1
+    } else {
+        quotient
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int(n: i32, span: std::ops::Range<usize>) -> Expr {
+        Expr::new(ExprKind::Literal(Value::Int(n)), span)
+    }
+
+    #[test]
+    fn evaluates_modulo_exponent_and_floor_divide() {
+        let mut interp = Interpreter::new();
+
+        let modulo = Expr::new(
+            ExprKind::Modulo {
+                left: Box::new(int(7, 0..1)),
+                right: Box::new(int(2, 2..3)),
+            },
+            0..3,
+        );
+        assert_eq!(interp.eval(&modulo), Ok(Value::Int(1)));
+
+        let exponent = Expr::new(
+            ExprKind::Exponent {
+                left: Box::new(int(2, 0..1)),
+                right: Box::new(int(10, 2..4)),
+            },
+            0..4,
+        );
+        assert_eq!(interp.eval(&exponent), Ok(Value::Int(1024)));
+
+        let floor_div = Expr::new(
+            ExprKind::FloorDivide {
+                left: Box::new(int(-7, 0..2)),
+                right: Box::new(int(2, 3..4)),
+            },
+            0..4,
+        );
+        assert_eq!(interp.eval(&floor_div), Ok(Value::Int(-4)));
+    }
+
+    #[test]
+    fn evaluates_bitwise_operators() {
+        let mut interp = Interpreter::new();
+
+        let bit_and = Expr::new(
+            ExprKind::BitAnd {
+                left: Box::new(int(0b110, 0..3)),
+                right: Box::new(int(0b011, 4..7)),
+            },
+            0..7,
+        );
+        assert_eq!(interp.eval(&bit_and), Ok(Value::Int(0b010)));
+
+        let bit_or = Expr::new(
+            ExprKind::BitOr {
+                left: Box::new(int(0b100, 0..3)),
+                right: Box::new(int(0b001, 4..7)),
+            },
+            0..7,
+        );
+        assert_eq!(interp.eval(&bit_or), Ok(Value::Int(0b101)));
+
+        let bit_xor = Expr::new(
+            ExprKind::BitXor {
+                left: Box::new(int(0b110, 0..3)),
+                right: Box::new(int(0b011, 4..7)),
+            },
+            0..7,
+        );
+        assert_eq!(interp.eval(&bit_xor), Ok(Value::Int(0b101)));
+    }
+
+    #[test]
+    fn evaluates_unary_negation() {
+        let mut interp = Interpreter::new();
+        let neg = Expr::new(ExprKind::Neg(Box::new(int(5, 1..2))), 0..2);
+        assert_eq!(interp.eval(&neg), Ok(Value::Int(-5)));
+    }
+
+    #[test]
+    fn apply_boxed_op_applies_the_operator() {
+        use crate::ast::BoxedOp;
+
+        let interp = Interpreter::new();
+        assert_eq!(
+            interp.apply_boxed_op(BoxedOp::Add, Value::Int(1), Value::Int(2)),
+            Ok(Value::Int(3))
+        );
+    }
+
+    // `Stmt::FunctionCall` has no return slot to bind a result to, so a
+    // successful call to a boxed op can't be observed from the outside —
+    // `apply_boxed_op_applies_the_operator` above is what actually covers
+    // the arithmetic. What `exec` *does* surface is the arity check, so
+    // that's what this asserts on.
+    #[test]
+    fn calling_a_boxed_op_with_wrong_arity_is_a_type_mismatch() {
+        use crate::ast::{BoxedOp, Iden};
+
+        let mut interp = Interpreter::new();
+        // f = \+;
+        interp
+            .run(&[Stmt::VarAssignment {
+                iden: Iden("f".to_owned()),
+                value: Expr::new(ExprKind::OpFunction(BoxedOp::Add), 0..2),
+            }])
+            .unwrap();
+
+        // f(1);
+        let err = interp
+            .exec(&Stmt::FunctionCall {
+                iden: Iden("f".to_owned()),
+                args: vec![int(1, 0..1)],
+            })
+            .unwrap_err();
+        assert_eq!(err.kind, ExecErrorKind::TypeMismatch);
+    }
+}
@@ -0,0 +1,205 @@
+//! Token definitions and the scanner that turns AbleScript source text
+//! into a stream of `SpannedToken`s for the parser.
+
+use std::ops::Range;
+
+use crate::variables::Abool;
+
+/// A single lexical token.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    // Literals
+    Integer(i32),
+    Boolean(bool),
+    Aboolean(Abool),
+    String(String),
+    Identifier(String),
+    Nul,
+
+    // Punctuation
+    LeftParenthesis,
+    RightParenthesis,
+    Comma,
+    Semicolon,
+    Assignment,
+    Backslash,
+
+    // Compound assignment
+    AddAssign,
+    SubAssign,
+    MulAssign,
+    DivAssign,
+    BitAndAssign,
+    BitOrAssign,
+
+    // Keywords
+    Print,
+
+    // Arithmetic
+    Addition,
+    Subtract,
+    Multiply,
+    Divide,
+    Modulo,
+    Exponent,
+    FloorDivide,
+
+    // Bitwise
+    BitAnd,
+    BitOr,
+    BitXor,
+
+    // Comparison
+    OpLt,
+    OpGt,
+    OpEq,
+    OpNeq,
+
+    // Logical
+    LogAnd,
+    LogOr,
+    LogNot,
+}
+
+pub type SpannedToken = (Token, Range<usize>);
+
+/// Scans AbleScript source text into `SpannedToken`s ahead of time so the
+/// parser can `peek`/`next` through them without re-lexing.
+pub struct Lexer<'a> {
+    source: &'a str,
+    tokens: Vec<SpannedToken>,
+    cursor: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Lexer {
+            source,
+            tokens: tokenize(source),
+            cursor: 0,
+        }
+    }
+
+    pub fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.cursor).map(|(tok, _)| tok)
+    }
+
+    // Not actually `Iterator::next` — it yields `SpannedToken`s alongside a
+    // separate `peek`, which is what every call site in the parser expects;
+    // renaming would ripple through all of them for no behavioral change.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<SpannedToken> {
+        let tok = self.tokens.get(self.cursor).cloned();
+        if tok.is_some() {
+            self.cursor += 1;
+        }
+        tok
+    }
+
+    /// Span of the most recently consumed token, used for error reporting
+    /// when no token is in hand.
+    pub fn span(&self) -> Range<usize> {
+        self.tokens
+            .get(self.cursor.saturating_sub(1))
+            .map(|(_, span)| span.clone())
+            .unwrap_or(self.source.len()..self.source.len())
+    }
+}
+
+fn tokenize(source: &str) -> Vec<SpannedToken> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    macro_rules! push {
+        ($tok:expr, $len:expr) => {{
+            tokens.push(($tok, i..i + $len));
+            i += $len;
+        }};
+    }
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b' ' | b'\t' | b'\r' | b'\n' => i += 1,
+            b'(' => push!(Token::LeftParenthesis, 1),
+            b')' => push!(Token::RightParenthesis, 1),
+            b',' => push!(Token::Comma, 1),
+            b';' => push!(Token::Semicolon, 1),
+            b'\\' => push!(Token::Backslash, 1),
+            b'=' => {
+                if bytes.get(i + 1) == Some(&b'=') {
+                    push!(Token::OpEq, 2)
+                } else {
+                    push!(Token::Assignment, 1)
+                }
+            }
+            b'!' => {
+                if bytes.get(i + 1) == Some(&b'=') {
+                    push!(Token::OpNeq, 2)
+                } else {
+                    push!(Token::LogNot, 1)
+                }
+            }
+            b'<' => push!(Token::OpLt, 1),
+            b'>' => push!(Token::OpGt, 1),
+            b'&' if bytes.get(i + 1) == Some(&b'&') => push!(Token::LogAnd, 2),
+            b'&' if bytes.get(i + 1) == Some(&b'=') => push!(Token::BitAndAssign, 2),
+            b'&' => push!(Token::BitAnd, 1),
+            b'|' if bytes.get(i + 1) == Some(&b'|') => push!(Token::LogOr, 2),
+            b'|' if bytes.get(i + 1) == Some(&b'=') => push!(Token::BitOrAssign, 2),
+            b'|' => push!(Token::BitOr, 1),
+            b'~' => push!(Token::BitXor, 1),
+            b'%' => push!(Token::Modulo, 1),
+            b'^' => push!(Token::Exponent, 1),
+            b'+' if bytes.get(i + 1) == Some(&b'=') => push!(Token::AddAssign, 2),
+            b'+' => push!(Token::Addition, 1),
+            b'-' if bytes.get(i + 1) == Some(&b'=') => push!(Token::SubAssign, 2),
+            b'-' => push!(Token::Subtract, 1),
+            b'*' if bytes.get(i + 1) == Some(&b'=') => push!(Token::MulAssign, 2),
+            b'*' => push!(Token::Multiply, 1),
+            b'/' if bytes.get(i + 1) == Some(&b'/') => push!(Token::FloorDivide, 2),
+            b'/' if bytes.get(i + 1) == Some(&b'=') => push!(Token::DivAssign, 2),
+            b'/' => push!(Token::Divide, 1),
+            b'"' => {
+                let start = i;
+                i += 1;
+                let content_start = i;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += 1;
+                }
+                let content = source[content_start..i].to_owned();
+                i += 1; // closing quote
+                tokens.push((Token::String(content), start..i));
+            }
+            b'0'..=b'9' => {
+                let start = i;
+                while i < bytes.len() && bytes[i].is_ascii_digit() {
+                    i += 1;
+                }
+                let value: i32 = source[start..i].parse().unwrap_or_default();
+                tokens.push((Token::Integer(value), start..i));
+            }
+            b'a'..=b'z' | b'A'..=b'Z' | b'_' => {
+                let start = i;
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+                let word = &source[start..i];
+                let tok = match word {
+                    "true" => Token::Boolean(true),
+                    "false" => Token::Boolean(false),
+                    "never" => Token::Aboolean(Abool::Never),
+                    "sometimes" => Token::Aboolean(Abool::Sometimes),
+                    "always" => Token::Aboolean(Abool::Always),
+                    "nul" => Token::Nul,
+                    "print" => Token::Print,
+                    _ => Token::Identifier(word.to_owned()),
+                };
+                tokens.push((tok, start..i));
+            }
+            _ => i += 1,
+        }
+    }
+
+    tokens
+}
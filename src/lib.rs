@@ -0,0 +1,6 @@
+pub mod ast;
+pub mod error;
+pub mod interpreter;
+pub mod lexer;
+pub mod parser;
+pub mod variables;
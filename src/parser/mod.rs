@@ -0,0 +1,77 @@
+//! The parser: turns a token stream into a sequence of `Stmt`s.
+
+pub mod ops;
+
+pub use crate::ast::{BoxedOp, Expr, ExprKind, Iden, Stmt};
+pub use crate::error::{Error, ErrorKind};
+pub use crate::lexer::{Lexer, SpannedToken, Token};
+pub use crate::variables::{Abool, Value};
+
+/// Either half of what a statement boundary in `parse_ops` can produce:
+/// most statements are genuine `Stmt`s, but a bare expression followed by
+/// `;` is also a legal parse result at the statement layer (`parse` drops
+/// it).
+#[derive(Debug)]
+pub enum ParserNode {
+    Expr(Expr),
+    Stmt(Stmt),
+}
+
+impl From<Expr> for ParserNode {
+    fn from(expr: Expr) -> Self {
+        ParserNode::Expr(expr)
+    }
+}
+
+impl From<Stmt> for ParserNode {
+    fn from(stmt: Stmt) -> Self {
+        ParserNode::Stmt(stmt)
+    }
+}
+
+pub type ParseResult = Result<ParserNode, Error>;
+
+pub struct Parser<'a> {
+    pub(crate) lexer: Lexer<'a>,
+    /// T-Dark mode: when set, string/identifier literals have `lang`
+    /// replaced with `script`.
+    pub(crate) tdark: bool,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(source: &'a str, tdark: bool) -> Self {
+        Parser {
+            lexer: Lexer::new(source),
+            tdark,
+        }
+    }
+
+    pub fn parse(&mut self) -> Result<Vec<Stmt>, Error> {
+        let mut stmts = Vec::new();
+        while let Some(token) = self.lexer.next() {
+            match self.parse_ops(token)? {
+                ParserNode::Stmt(stmt) => stmts.push(stmt),
+                ParserNode::Expr(_) => {}
+            }
+        }
+        Ok(stmts)
+    }
+
+    /// Consume the next token, erroring if it isn't `expected`.
+    pub(crate) fn require(&mut self, expected: Token) -> Result<(), Error> {
+        match self.lexer.next() {
+            Some((token, _)) if token == expected => Ok(()),
+            Some((_, span)) => Err(Error::unexpected_token(span)),
+            None => Err(Error::end_of_token_stream()),
+        }
+    }
+
+    /// Build an "unexpected token" error for `token`, or for the current
+    /// lexer position if `token` is `None`.
+    pub(crate) fn unexpected_token(&mut self, token: Option<SpannedToken>) -> Error {
+        match token {
+            Some((_, span)) => Error::unexpected_token(span),
+            None => Error::unexpected_token(self.lexer.span()),
+        }
+    }
+}
@@ -2,76 +2,240 @@ use super::*;
 
 type ExprResult = Result<Expr, Error>;
 
-/// Generate infix expression by pattern left <op> right
-///
-/// Credits: `@! ! Reiter#4543`
-#[macro_export]
-macro_rules! gen_infix {
-    ($($fn_name: ident => $type: tt);*$(;)?) => {$(
-        /// Generated function for infix operator
-        fn $fn_name(&mut self, left: Expr) -> ExprResult {
-            self.lexer.next();
-            let next = self.lexer.next();
-            let right = self.parse_expr(next)?;
-            Ok(Expr::$type { left: Box::new(left), right: Box::new(right) })
-        })*
-    };
-}
-
 impl<'a> Parser<'a> {
     pub(super) fn parse_ops(&mut self, token: SpannedToken) -> ParseResult {
         // Statements
         match self.lexer.peek() {
             Some(Token::LeftParenthesis) => return self.fn_call(token),
-            Some(Token::Assignment) => return self.parse_assignment(token),
+            Some(Token::Assignment) => return self.parse_assignment(token, None),
+            Some(Token::AddAssign) => return self.parse_assignment(token, Some(Token::Addition)),
+            Some(Token::SubAssign) => return self.parse_assignment(token, Some(Token::Subtract)),
+            Some(Token::MulAssign) => return self.parse_assignment(token, Some(Token::Multiply)),
+            Some(Token::DivAssign) => return self.parse_assignment(token, Some(Token::Divide)),
+            Some(Token::BitAndAssign) => return self.parse_assignment(token, Some(Token::BitAnd)),
+            Some(Token::BitOrAssign) => return self.parse_assignment(token, Some(Token::BitOr)),
             _ => (),
         }
 
-        let mut buf: Expr = self.parse_expr(Some(token))?;
+        let atom = self.parse_expr(Some(token))?;
+        let buf = self.parse_expr_bp(atom, 0)?;
+
+        match self.lexer.peek() {
+            Some(Token::Print) => {
+                self.lexer.next();
+                self.require(Token::Semicolon)?;
+                Ok(Stmt::Print(buf).into())
+            }
+            Some(Token::Semicolon) => {
+                self.lexer.next();
+                Ok(buf.into())
+            }
+            // Anything else sitting after a fully-parsed expression (e.g.
+            // the stray `c` in `a + b c`) means the statement was malformed;
+            // erroring here keeps the lexer in sync instead of silently
+            // dropping the token and letting the next parse desync.
+            _ => {
+                let tok = self.lexer.next();
+                Err(self.unexpected_token(tok))
+            }
+        }
+    }
 
+    /// Parse an expression by precedence climbing (a Pratt parser).
+    ///
+    /// `left` is the atom (or sub-expression) already sitting to the left of
+    /// the cursor; `min_bp` is the minimum binding power an infix operator
+    /// must have to be folded into it here. An operator with a lower binding
+    /// power is left on the lexer for a caller further up the recursion to
+    /// pick up, which is what gives e.g. `a + b * c` the grouping
+    /// `a + (b * c)` instead of forcing left association on every operator.
+    pub(super) fn parse_expr_bp(&mut self, mut left: Expr, min_bp: u8) -> ExprResult {
         loop {
-            let peek = self.lexer.peek().clone();
-            buf = match peek {
-                // Print statement
-                Some(Token::Print) => {
-                    self.lexer.next();
-                    self.require(Token::Semicolon)?;
-                    return Ok(Stmt::Print(buf).into());
-                }
-                None => return Ok(buf.into()),
+            let op_token = match self.lexer.peek() {
+                Some(op) => op.clone(),
+                None => return Ok(left),
+            };
 
-                // An expression
-                _ => self.parse_operation(peek, buf)?,
+            let lbp = match Self::binding_power(&op_token) {
+                Some(lbp) => lbp,
+                None => return Ok(left),
+            };
+
+            if lbp < min_bp {
+                return Ok(left);
             }
+
+            // Exponentiation is right-associative: `a ^ b ^ c` is `a ^ (b ^ c)`,
+            // so the right operand recurses at the same binding power rather
+            // than `lbp + 1`.
+            let right_min_bp = if matches!(op_token, Token::Exponent) {
+                lbp
+            } else {
+                lbp + 1
+            };
+
+            let op = self.lexer.next().expect("peeked token disappeared"); // Eat the operator
+            let next = self.lexer.next();
+            let atom = self.parse_expr(next)?;
+            let right = self.parse_expr_bp(atom, right_min_bp)?;
+            left = Self::fold_infix(op, left, right)?;
         }
     }
 
-    /// Match and perform
-    pub(super) fn parse_operation(&mut self, token: Option<SpannedToken>, buf: Expr) -> ExprResult {
-        match token {
-            Some((Token::Addition, span)) => self.addition(buf),
-            Some((Token::Subtract, span)) => self.subtract(buf),
-            Some((Token::Multiply, span)) => self.multiply(buf),
-            Some((Token::Divide, span)) => self.divide(buf),
-            Some((Token::OpLt, span)) => self.cmplt(buf),
-            Some((Token::OpGt, span)) => self.cmpgt(buf),
-            Some((Token::OpEq, span)) => self.cmpeq(buf),
-            Some((Token::OpNeq, span)) => self.cmpneq(buf),
-            Some((Token::LogAnd, span)) => self.logand(buf),
-            Some((Token::LogOr, span)) => self.logor(buf),
-            Some((Token::LeftParenthesis, span)) | Some((_, span)) => {
-                Err(Error::unexpected_token(span))
-            }
-            None => Err(Error::end_of_token_stream()),
+    /// Left binding power of an infix operator, used by `parse_expr_bp` for
+    /// precedence climbing. A token not covered here is not an infix
+    /// operator and ends expression parsing.
+    fn binding_power(token: &Token) -> Option<u8> {
+        Some(match token {
+            Token::LogOr => 1,
+            Token::LogAnd => 2,
+            Token::BitOr => 3,
+            Token::BitXor => 4,
+            Token::BitAnd => 5,
+            Token::OpEq | Token::OpNeq => 6,
+            Token::OpLt | Token::OpGt => 7,
+            Token::Addition | Token::Subtract => 8,
+            Token::Multiply | Token::Divide | Token::Modulo | Token::FloorDivide => 9,
+            Token::Exponent => 10,
+            _ => return None,
+        })
+    }
+
+    /// Fold a left/right operand pair around the infix operator `op`.
+    ///
+    /// This is the table-driven replacement for the old `gen_infix!`
+    /// generated methods: each operator token maps directly to its `Expr`
+    /// variant.
+    fn fold_infix(op: SpannedToken, left: Expr, right: Expr) -> ExprResult {
+        let span = left.span.start..right.span.end;
+        match op {
+            (Token::Addition, _) => Ok(Expr::new(
+                ExprKind::Add {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+                span,
+            )),
+            (Token::Subtract, _) => Ok(Expr::new(
+                ExprKind::Subtract {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+                span,
+            )),
+            (Token::Multiply, _) => Ok(Expr::new(
+                ExprKind::Multiply {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+                span,
+            )),
+            (Token::Divide, _) => Ok(Expr::new(
+                ExprKind::Divide {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+                span,
+            )),
+            (Token::OpLt, _) => Ok(Expr::new(
+                ExprKind::Lt {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+                span,
+            )),
+            (Token::OpGt, _) => Ok(Expr::new(
+                ExprKind::Gt {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+                span,
+            )),
+            (Token::OpEq, _) => Ok(Expr::new(
+                ExprKind::Eq {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+                span,
+            )),
+            (Token::OpNeq, _) => Ok(Expr::new(
+                ExprKind::Neq {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+                span,
+            )),
+            (Token::LogAnd, _) => Ok(Expr::new(
+                ExprKind::And {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+                span,
+            )),
+            (Token::LogOr, _) => Ok(Expr::new(
+                ExprKind::Or {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+                span,
+            )),
+            (Token::BitAnd, _) => Ok(Expr::new(
+                ExprKind::BitAnd {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+                span,
+            )),
+            (Token::BitOr, _) => Ok(Expr::new(
+                ExprKind::BitOr {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+                span,
+            )),
+            (Token::BitXor, _) => Ok(Expr::new(
+                ExprKind::BitXor {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+                span,
+            )),
+            (Token::Modulo, _) => Ok(Expr::new(
+                ExprKind::Modulo {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+                span,
+            )),
+            (Token::Exponent, _) => Ok(Expr::new(
+                ExprKind::Exponent {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+                span,
+            )),
+            (Token::FloorDivide, _) => Ok(Expr::new(
+                ExprKind::FloorDivide {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+                span,
+            )),
+            (Token::LeftParenthesis, span) | (_, span) => Err(Error::unexpected_token(span)),
         }
     }
 
-    fn parse_assignment(&mut self, token: SpannedToken) -> ParseResult {
-        let start = token.1.start;
+    /// Parse `iden = expr;` and, when `compound` is given, the desugared
+    /// compound forms `iden += expr;`, `iden -= expr;`, etc. `compound` is
+    /// the infix token the compound operator stands for (e.g. `Token::Addition`
+    /// for `+=`), which is folded with the identifier as the left operand and
+    /// the parsed right-hand side as the right operand.
+    fn parse_assignment(&mut self, token: SpannedToken, compound: Option<Token>) -> ParseResult {
+        let iden_span = token.1.clone();
         self.lexer.next(); // Eat
 
         // Extract identifier
-        let iden = if let Token::Identifier(i) = token {
+        let iden = if let Token::Identifier(i) = token.0 {
             Iden(i)
         } else {
             return Err(Error {
@@ -81,39 +245,22 @@ impl<'a> Parser<'a> {
         };
 
         let next = self.lexer.next();
-        let mut value = self.parse_expr(next)?;
+        let atom = self.parse_expr(next)?;
+        let rhs = self.parse_expr_bp(atom, 0)?;
 
-        loop {
-            let peek = self.lexer.peek().clone();
-            value = match peek {
-                Some(Token::Semicolon) => break,
-                None => {
-                    return Err(Error {
-                        kind: ErrorKind::EndOfTokenStream,
-                        span: self.lexer.span(),
-                    })
-                }
-                Some(t) => self.parse_operation(Some(t), value)?,
-            };
-        }
+        self.require(Token::Semicolon)?;
 
-        self.lexer.next();
+        let value = match compound {
+            Some(op) => Self::fold_infix(
+                (op, iden_span.clone()),
+                Expr::new(ExprKind::Identifier(iden.clone()), iden_span),
+                rhs,
+            )?,
+            None => rhs,
+        };
 
         Ok(Stmt::VarAssignment { iden, value }.into())
     }
-    // Generate infix
-    gen_infix! {
-        addition => Add;
-        subtract => Subtract;
-        multiply => Multiply;
-        divide => Divide;
-        cmplt => Lt;
-        cmpgt => Gt;
-        cmpeq => Eq;
-        cmpneq => Neq;
-        logand => And;
-        logor => Or;
-    }
 
     /// Ensure that input token is an expression
     pub(super) fn parse_expr(&mut self, token: Option<SpannedToken>) -> ExprResult {
@@ -143,12 +290,27 @@ impl<'a> Parser<'a> {
             Token::LogNot => {
                 let next = self.lexer.next();
                 let expr = self.parse_expr(next)?;
-                Ok(Expr::new(
-                    ExprKind::Not(Box::new(expr)),
-                    span.start..expr.span.end,
-                ))
+                let end = expr.span.end;
+                Ok(Expr::new(ExprKind::Not(Box::new(expr)), span.start..end))
+            }
+            // `-` is also `Subtract`'s infix token; it's only read as unary
+            // negation here, in atom position (where `parse_expr_bp`/
+            // `fold_infix` would never see it, since they only run once an
+            // atom is already in hand).
+            Token::Subtract => {
+                let next = self.lexer.next();
+                let expr = self.parse_expr(next)?;
+                let end = expr.span.end;
+                Ok(Expr::new(ExprKind::Neg(Box::new(expr)), span.start..end))
             }
             Token::LeftParenthesis => self.parse_paren(),
+            Token::Backslash => {
+                let next = self.lexer.next();
+                let (op_token, op_span) = next.ok_or(Error::end_of_token_stream())?;
+                let op = BoxedOp::from_token(&op_token)
+                    .ok_or_else(|| Error::unexpected_token(op_span.clone()))?;
+                Ok(Expr::new(ExprKind::OpFunction(op), span.start..op_span.end))
+            }
             _ => Err(Error::unexpected_token(span)),
         }
     }
@@ -156,45 +318,40 @@ impl<'a> Parser<'a> {
     /// Parse parenthesieted expression
     pub(super) fn parse_paren(&mut self) -> ExprResult {
         let next = self.lexer.next();
-        let mut buf = self.parse_expr(next)?;
-        loop {
-            let peek = self.lexer.peek().clone();
-            buf = match peek {
-                Some(Token::RightParenthesis) => {
-                    self.lexer.next();
-                    return Ok(buf);
-                }
-                None => return Ok(buf),
-                Some(t) => self.parse_operation(Some(t), buf)?,
-            };
-        }
+        let atom = self.parse_expr(next)?;
+        let buf = self.parse_expr_bp(atom, 0)?;
+        // A leftover token here (anything but `)`) means the parenthesised
+        // expression was malformed, e.g. `(a + b c)` — error instead of
+        // silently discarding it and leaving the lexer out of sync.
+        self.require(Token::RightParenthesis)?;
+        Ok(buf)
     }
 
     /// Parse function call
-    fn fn_call(&mut self, token: Token) -> ParseResult {
-        let iden = if let Token::Identifier(i) = token {
+    fn fn_call(&mut self, token: SpannedToken) -> ParseResult {
+        let iden = if let Token::Identifier(i) = token.0 {
             Iden(i)
         } else {
             return Err(Error {
                 kind: ErrorKind::InvalidIdentifier,
-                span: self.lexer.span(),
+                span: token.1,
             });
         };
 
-        self.lexer.next();
+        self.lexer.next(); // Eat `(`
         let mut args = Vec::new();
         loop {
             let next = self.lexer.next();
 
             // No argument function
-            if matches!(next, Some(Token::RightParenthesis)) {
+            if matches!(next, Some((Token::RightParenthesis, _))) {
                 break;
             }
 
             args.push(self.parse_expr(next)?);
             match self.lexer.next() {
-                Some(Token::RightParenthesis) => break,
-                Some(Token::Comma) => continue,
+                Some((Token::RightParenthesis, _)) => break,
+                Some((Token::Comma, _)) => continue,
                 _ => return Err(self.unexpected_token(None)),
             }
         }
@@ -202,3 +359,179 @@ impl<'a> Parser<'a> {
         Ok(Stmt::FunctionCall { iden, args }.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_one(src: &str) -> Result<ParserNode, Error> {
+        let mut parser = Parser::new(src, false);
+        let token = parser.lexer.next().expect("at least one token");
+        parser.parse_ops(token)
+    }
+
+    fn parse_expr_stmt(src: &str) -> Expr {
+        match parse_one(src).expect("expected a successful parse") {
+            ParserNode::Expr(expr) => expr,
+            ParserNode::Stmt(stmt) => panic!("expected a bare expression, got {:?}", stmt),
+        }
+    }
+
+    #[test]
+    fn precedence_climbing_groups_multiply_before_add() {
+        let expr = parse_expr_stmt("1 + 2 * 3;");
+        match expr.kind {
+            ExprKind::Add { left, right } => {
+                assert!(matches!(left.kind, ExprKind::Literal(Value::Int(1))));
+                assert!(matches!(right.kind, ExprKind::Multiply { .. }));
+            }
+            other => panic!("expected Add at the root, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn precedence_climbing_groups_and_before_or() {
+        let expr = parse_expr_stmt("true || false && true;");
+        match expr.kind {
+            ExprKind::Or { left, right } => {
+                assert!(matches!(left.kind, ExprKind::Literal(Value::Bool(true))));
+                assert!(matches!(right.kind, ExprKind::And { .. }));
+            }
+            other => panic!("expected Or at the root, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn stray_token_inside_paren_is_an_error() {
+        assert!(parse_one("(1 + 2 3);").is_err());
+    }
+
+    #[test]
+    fn trailing_token_after_expression_is_an_error() {
+        assert!(parse_one("1 2;").is_err());
+    }
+
+    #[test]
+    fn exponent_is_right_associative() {
+        // `2 ^ 3 ^ 2` is `2 ^ (3 ^ 2)`, not `(2 ^ 3) ^ 2`.
+        let expr = parse_expr_stmt("2 ^ 3 ^ 2;");
+        match expr.kind {
+            ExprKind::Exponent { left, right } => {
+                assert!(matches!(left.kind, ExprKind::Literal(Value::Int(2))));
+                assert!(matches!(right.kind, ExprKind::Exponent { .. }));
+            }
+            other => panic!("expected Exponent at the root, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn bitwise_and_binds_tighter_than_bitwise_or() {
+        let expr = parse_expr_stmt("1 | 2 & 3;");
+        match expr.kind {
+            ExprKind::BitOr { left, right } => {
+                assert!(matches!(left.kind, ExprKind::Literal(Value::Int(1))));
+                assert!(matches!(right.kind, ExprKind::BitAnd { .. }));
+            }
+            other => panic!("expected BitOr at the root, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn modulo_and_floor_divide_share_multiplicative_precedence() {
+        let expr = parse_expr_stmt("1 + 7 % 2;");
+        match expr.kind {
+            ExprKind::Add { right, .. } => {
+                assert!(matches!(right.kind, ExprKind::Modulo { .. }));
+            }
+            other => panic!("expected Add at the root, got {:?}", other),
+        }
+
+        let expr = parse_expr_stmt("1 + 7 // 2;");
+        match expr.kind {
+            ExprKind::Add { right, .. } => {
+                assert!(matches!(right.kind, ExprKind::FloorDivide { .. }));
+            }
+            other => panic!("expected Add at the root, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn boxed_infix_operator_parses_to_op_function() {
+        let expr = parse_expr_stmt("\\+;");
+        assert!(matches!(
+            expr.kind,
+            ExprKind::OpFunction(BoxedOp::Add)
+        ));
+    }
+
+    #[test]
+    fn boxing_a_non_boxable_token_is_an_error() {
+        assert!(parse_one("\\print;").is_err());
+    }
+
+    #[test]
+    fn unary_minus_parses_as_neg() {
+        let expr = parse_expr_stmt("-5;");
+        assert!(matches!(
+            expr.kind,
+            ExprKind::Neg(ref inner) if matches!(inner.kind, ExprKind::Literal(Value::Int(5)))
+        ));
+    }
+
+    #[test]
+    fn unary_minus_binds_tighter_than_infix_subtract() {
+        // `-a - b` is `(-a) - b`, not `-(a - b)`.
+        let expr = parse_expr_stmt("-1 - 2;");
+        match expr.kind {
+            ExprKind::Subtract { left, .. } => {
+                assert!(matches!(left.kind, ExprKind::Neg(_)));
+            }
+            other => panic!("expected Subtract at the root, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn compound_assignment_desugars_to_binary_op_on_self() {
+        match parse_one("x += 1;").expect("expected a successful parse") {
+            ParserNode::Stmt(Stmt::VarAssignment { iden, value }) => {
+                assert_eq!(iden, Iden("x".to_owned()));
+                match value.kind {
+                    ExprKind::Add { left, right } => {
+                        assert!(matches!(left.kind, ExprKind::Identifier(ref i) if *i == Iden("x".to_owned())));
+                        assert!(matches!(right.kind, ExprKind::Literal(Value::Int(1))));
+                    }
+                    other => panic!("expected Add, got {:?}", other),
+                }
+            }
+            other => panic!("expected a VarAssignment, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn all_compound_assignment_operators_parse() {
+        for (src, expect_kind) in [
+            ("x += 1;", "Add"),
+            ("x -= 1;", "Subtract"),
+            ("x *= 1;", "Multiply"),
+            ("x /= 1;", "Divide"),
+            ("x &= 1;", "BitAnd"),
+            ("x |= 1;", "BitOr"),
+        ] {
+            match parse_one(src).unwrap_or_else(|e| panic!("{} failed to parse: {:?}", src, e)) {
+                ParserNode::Stmt(Stmt::VarAssignment { value, .. }) => {
+                    let kind = match value.kind {
+                        ExprKind::Add { .. } => "Add",
+                        ExprKind::Subtract { .. } => "Subtract",
+                        ExprKind::Multiply { .. } => "Multiply",
+                        ExprKind::Divide { .. } => "Divide",
+                        ExprKind::BitAnd { .. } => "BitAnd",
+                        ExprKind::BitOr { .. } => "BitOr",
+                        ref other => panic!("{} produced unexpected kind {:?}", src, other),
+                    };
+                    assert_eq!(kind, expect_kind, "for {}", src);
+                }
+                other => panic!("expected a VarAssignment for {}, got {:?}", src, other),
+            }
+        }
+    }
+}
@@ -0,0 +1,26 @@
+//! Runtime value types shared between the parser (for literal tokens) and
+//! the interpreter (for evaluation results).
+
+use crate::ast::BoxedOp;
+
+/// An "ambiguous boolean": AbleScript's three-valued logic type, alongside
+/// the regular `bool`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Abool {
+    Never,
+    Sometimes,
+    Always,
+}
+
+/// A runtime value produced by evaluating an `Expr`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i32),
+    Bool(bool),
+    Abool(Abool),
+    Str(String),
+    Nul,
+    /// A boxed infix operator (`\+`, `\<`, ...), callable like any other
+    /// function value via `FunctionCall`.
+    BoxedOp(BoxedOp),
+}